@@ -45,23 +45,131 @@ use send_wrapper::SendWrapper;
 use std::sync::{RwLock, RwLockWriteGuard};
 
 use macroquad::miniquad as mq;
+use macroquad::texture::Texture2D;
 use macroquad::window::get_internal_gl;
 use yakui_miniquad::*;
 
 pub use macroquad;
 
-struct Yakui(YakuiMiniQuad, usize);
+struct Yakui {
+    inner: YakuiMiniQuad,
+    input_subscriber: usize,
+    touch_id: Option<u64>,
+    automatic_scale_factor: bool,
+    scale_factor: f32,
+}
+
+/// An owned yakui context bound to macroquad.
+///
+/// The free functions in this crate ([`start`], [`finish`], [`ui`], [`cfg`],
+/// [`draw`], ...) are a thin wrapper around a single default `YakuiInstance`
+/// kept in a global, for convenience and backward compatibility. Construct
+/// your own `YakuiInstance` instead if you need more than one independent UI
+/// at once, e.g. a debug overlay on top of a game HUD with a different scale
+/// factor. Each instance registers its own input subscriber with macroquad,
+/// so instances don't interfere with each other's input routing; draw them
+/// in whatever order you'd like them layered.
+pub struct YakuiInstance(SendWrapper<Yakui>);
+
+impl YakuiInstance {
+    /// Creates a new, independent yakui context.
+    pub fn new() -> Self {
+        Self(SendWrapper::new(Yakui::new()))
+    }
+
+    /// Returns true if the last mouse or keyboard event was sunk by this instance, and should not be handled by your game.
+    pub fn has_input_focus(&self) -> bool {
+        self.0.has_input_focus()
+    }
 
-// Global variable and global functions because it's more like macroquad way
-static YAKUI: RwLock<Option<SendWrapper<Yakui>>> = RwLock::new(None);
+    /// Returns true if the last keyboard event was sunk by this instance, and should not be handled by your game.
+    pub fn has_keyboard_focus(&self) -> bool {
+        self.0.has_keyboard_focus()
+    }
+
+    /// Returns true if the last mouse event was sunk by this instance, and should not be handled by your game.
+    pub fn has_mouse_focus(&self) -> bool {
+        self.0.has_mouse_focus()
+    }
+
+    /// Binds this yakui context to the current thread.
+    pub fn start(&mut self) {
+        self.0.start();
+    }
+
+    /// Finishes this yakui context and prepares it for rendering.
+    pub fn finish(&mut self) {
+        self.0.finish();
+    }
+
+    /// Allows you to submit commands to this yakui context inside the scope of the closure passed, calls [`YakuiInstance::start`] and [`YakuiInstance::finish`] for you.
+    pub fn ui<F: FnOnce(&mut yakui_core::Yakui)>(&mut self, f: F) {
+        self.0.ui(f)
+    }
+
+    /// Allows you configure this yakui context within the scope of the closure passed, if you need to.
+    pub fn cfg<F: FnOnce(&mut yakui_core::Yakui)>(&mut self, f: F) {
+        f(self.0.ctx())
+    }
+
+    /// Draws this instance's ui. Must be called after `finish`/`ui` and once per frame.
+    pub fn draw(&mut self) {
+        self.0.draw()
+    }
+
+    /// Registers a macroquad-loaded `Texture2D` for use in this instance's yakui `Image` widget,
+    /// returning the id to pass to it.
+    ///
+    /// The caller keeps ownership of `texture`; yakui only holds onto the
+    /// underlying miniquad handle and never frees it, so the texture must
+    /// outlive every `draw` call it's used in.
+    pub fn add_texture(&mut self, texture: &Texture2D) -> yakui_core::TextureId {
+        self.0.add_texture(texture)
+    }
 
-fn get_yakui() -> RwLockWriteGuard<'static, Option<SendWrapper<Yakui>>> {
+    /// Re-points a texture id previously returned by [`YakuiInstance::add_texture`] at a different macroquad texture.
+    pub fn update_texture(&mut self, id: yakui_core::TextureId, texture: &Texture2D) {
+        self.0.update_texture(id, texture);
+    }
+
+    /// Unregisters a texture id previously returned by [`YakuiInstance::add_texture`].
+    pub fn remove_texture(&mut self, id: yakui_core::TextureId) {
+        self.0.remove_texture(id);
+    }
+
+    /// Overrides the scale factor this instance lays out at, instead of
+    /// automatically tracking macroquad's DPI scale. Implies
+    /// `set_automatic_scale_factor(false)`.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.0.set_scale_factor(scale_factor);
+    }
+
+    /// Switches this instance between automatically tracking macroquad's DPI
+    /// scale every [`YakuiInstance::start`]/[`YakuiInstance::ui`] (the
+    /// default) and using whatever was last passed to
+    /// [`YakuiInstance::set_scale_factor`].
+    pub fn set_automatic_scale_factor(&mut self, automatic: bool) {
+        self.0.set_automatic_scale_factor(automatic);
+    }
+}
+
+impl Default for YakuiInstance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Global variable and global functions because it's more like macroquad way,
+// backed by a default `YakuiInstance` so existing callers keep working.
+static YAKUI: RwLock<Option<YakuiInstance>> = RwLock::new(None);
+
+fn get_yakui() -> RwLockWriteGuard<'static, Option<YakuiInstance>> {
     match YAKUI.try_write() {
         Ok(mut yakui) => {
             if yakui.is_some() {
                 yakui
             } else {
-                *yakui = Some(SendWrapper::new(Yakui::new()));
+                *yakui = Some(YakuiInstance::new());
                 yakui
             }
         }
@@ -73,51 +181,148 @@ fn get_yakui() -> RwLockWriteGuard<'static, Option<SendWrapper<Yakui>>> {
 
 impl Yakui {
     fn new() -> Self {
-        Self(
-            YakuiMiniQuad::new(unsafe { get_internal_gl() }.quad_context),
-            macroquad::input::utils::register_input_subscriber(),
-        )
+        Self {
+            inner: YakuiMiniQuad::new(unsafe { get_internal_gl() }.quad_context),
+            input_subscriber: macroquad::input::utils::register_input_subscriber(),
+            touch_id: None,
+            automatic_scale_factor: true,
+            scale_factor: 1.0,
+        }
     }
 
     fn start(&mut self) {
-        macroquad::input::utils::repeat_all_miniquad_input(self, self.1);
-        self.0.start();
+        macroquad::input::utils::repeat_all_miniquad_input(self, self.input_subscriber);
+        self.sync_clipboard_in();
+        self.inner.ctx().set_scale_factor(self.scale_factor());
+        self.inner.start();
     }
 
     fn finish(&mut self) {
-        self.0.finish();
+        self.inner.finish();
+        self.sync_clipboard_out();
     }
 
     fn ui<F>(&mut self, f: F)
     where
         F: FnOnce(&mut yakui_core::Yakui),
     {
-        macroquad::input::utils::repeat_all_miniquad_input(self, self.1);
+        macroquad::input::utils::repeat_all_miniquad_input(self, self.input_subscriber);
+        self.sync_clipboard_in();
+        self.inner.ctx().set_scale_factor(self.scale_factor());
+
+        self.inner.run(f);
 
-        self.0.run(f);
+        self.sync_clipboard_out();
+    }
+
+    fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.automatic_scale_factor = false;
+        self.scale_factor = scale_factor;
+    }
+
+    fn set_automatic_scale_factor(&mut self, automatic: bool) {
+        self.automatic_scale_factor = automatic;
+    }
+
+    fn has_input_focus(&self) -> bool {
+        self.inner.has_input_focus()
+    }
+
+    fn has_keyboard_focus(&self) -> bool {
+        self.inner.has_keyboard_focus()
+    }
+
+    fn has_mouse_focus(&self) -> bool {
+        self.inner.has_mouse_focus()
+    }
+
+    fn ctx(&mut self) -> &mut yakui_core::Yakui {
+        self.inner.ctx()
+    }
+
+    /// The scale factor yakui is currently laying out at: macroquad's DPI
+    /// scale while automatic, or the last value passed to
+    /// `set_scale_factor` otherwise.
+    fn scale_factor(&self) -> f32 {
+        if self.automatic_scale_factor {
+            mq::window::dpi_scale()
+        } else {
+            self.scale_factor
+        }
+    }
+
+    // Only called from `start`/`ui`/`finish`, never from the `mq::EventHandler`
+    // methods: `clipboard_get` borrows the miniquad `Context` mutably, which
+    // is already borrowed while an event handler is running and panics with
+    // `BorrowedMutError` on wasm.
+    fn sync_clipboard_in(&mut self) {
+        self.inner.ctx().set_clipboard(read_system_clipboard());
+    }
+
+    fn sync_clipboard_out(&mut self) {
+        if let Some(text) = self.inner.ctx().take_clipboard() {
+            mq::window::clipboard_set(&text);
+        }
     }
 
     fn draw(&mut self) {
         let mut gl = unsafe { get_internal_gl() };
         // Ensure that macroquad's shapes are not going to be lost, and draw them now
         gl.flush();
-        self.0.draw(gl.quad_context);
+        self.inner.draw(gl.quad_context);
+    }
+
+    fn add_texture(&mut self, texture: &Texture2D) -> yakui_core::TextureId {
+        self.inner.add_texture(texture.raw_miniquad_id())
+    }
+
+    fn update_texture(&mut self, id: yakui_core::TextureId, texture: &Texture2D) {
+        self.inner.update_texture(id, texture.raw_miniquad_id());
+    }
+
+    fn remove_texture(&mut self, id: yakui_core::TextureId) {
+        self.inner.remove_texture(id);
+    }
+}
+
+/// Converts a pointer position reported by miniquad (in physical pixels) to
+/// the logical-pixel space yakui lays out and hit-tests in. yakui's scale
+/// factor only changes how it lays widgets out, not what coordinate space it
+/// expects pointer input in, so this conversion by the window's real DPI
+/// scale applies unconditionally — including in the default automatic mode,
+/// where it's needed on every HiDPI display, not only when a manual scale
+/// factor override is in effect.
+fn scale_point(x: f32, y: f32) -> (f32, f32) {
+    let window_scale = mq::window::dpi_scale();
+    if window_scale == 0.0 {
+        return (x, y);
     }
+    (x / window_scale, y / window_scale)
+}
+
+/// Reads the system clipboard.
+///
+/// On wasm, `clipboard_get` panics with a `BorrowedMutError` if called while
+/// a quad `Context` borrow is already live, but this is only ever called
+/// from `sync_clipboard_in`, i.e. from `start`/`ui`, never from an
+/// `mq::EventHandler` method, so no such borrow can be outstanding here.
+fn read_system_clipboard() -> String {
+    mq::window::clipboard_get().unwrap_or_default()
 }
 
 /// Returns true if the last mouse or keyboard event was sunk by yakui, and should not be handled by your game.
 pub fn has_input_focus() -> bool {
-    get_yakui().as_ref().unwrap().0.has_input_focus()
+    get_yakui().as_ref().unwrap().has_input_focus()
 }
 
 /// Returns true if the last keyboard event was sunk by yakui, and should not be handled by your game.
 pub fn has_keyboard_focus() -> bool {
-    get_yakui().as_ref().unwrap().0.has_keyboard_focus()
+    get_yakui().as_ref().unwrap().has_keyboard_focus()
 }
 
 /// Returns true if the last mouse event was sunk by yakui, and should not be handled by your game.
 pub fn has_mouse_focus() -> bool {
-    get_yakui().as_ref().unwrap().0.has_mouse_focus()
+    get_yakui().as_ref().unwrap().has_mouse_focus()
 }
 
 /// Binds the yakui context to the current thread.
@@ -137,7 +342,7 @@ pub fn ui<F: FnOnce(&mut yakui_core::Yakui)>(f: F) {
 
 /// Allows you configure the yakui context within the scope of the closure passed, if you need to.
 pub fn cfg<F: FnOnce(&mut yakui_core::Yakui)>(f: F) {
-    f(get_yakui().as_mut().unwrap().0.ctx());
+    get_yakui().as_mut().unwrap().cfg(f);
 }
 
 /// Draws the yakui ui. Must be called after `finish`/`ui` and once per frame.
@@ -145,36 +350,109 @@ pub fn draw() {
     get_yakui().as_mut().unwrap().draw()
 }
 
+/// Registers a macroquad-loaded `Texture2D` for use in yakui's `Image` widget,
+/// returning the id to pass to it.
+///
+/// The caller keeps ownership of `texture`; yakui only holds onto the
+/// underlying miniquad handle and never frees it, so the texture must outlive
+/// every [`draw`] call it's used in.
+pub fn add_texture(texture: &Texture2D) -> yakui_core::TextureId {
+    get_yakui().as_mut().unwrap().add_texture(texture)
+}
+
+/// Re-points a texture id previously returned by [`add_texture`] at a
+/// different macroquad texture.
+pub fn update_texture(id: yakui_core::TextureId, texture: &Texture2D) {
+    get_yakui().as_mut().unwrap().update_texture(id, texture);
+}
+
+/// Unregisters a texture id previously returned by [`add_texture`].
+pub fn remove_texture(id: yakui_core::TextureId) {
+    get_yakui().as_mut().unwrap().remove_texture(id);
+}
+
+/// Returns macroquad's current DPI scale factor (physical pixels per logical pixel).
+pub fn dpi_scale() -> f32 {
+    mq::window::dpi_scale()
+}
+
+/// Overrides the scale factor yakui lays out at, instead of automatically
+/// tracking macroquad's DPI scale. Useful for forcing crisp 1:1 layout
+/// (`set_scale_factor(1.0)`) or a custom UI zoom.
+pub fn set_scale_factor(scale_factor: f32) {
+    get_yakui().as_mut().unwrap().set_scale_factor(scale_factor);
+}
+
+/// Switches between automatically tracking macroquad's DPI scale every frame
+/// (the default) and using whatever was last passed to [`set_scale_factor`].
+pub fn set_automatic_scale_factor(automatic: bool) {
+    get_yakui()
+        .as_mut()
+        .unwrap()
+        .set_automatic_scale_factor(automatic);
+}
+
 impl mq::EventHandler for Yakui {
     fn update(&mut self) {}
 
     fn draw(&mut self) {}
 
     fn mouse_motion_event(&mut self, x: f32, y: f32) {
-        self.0.mouse_motion_event(x, y);
+        let (x, y) = scale_point(x, y);
+        self.inner.mouse_motion_event(x, y);
     }
 
     fn mouse_wheel_event(&mut self, dx: f32, dy: f32) {
-        self.0.mouse_wheel_event(dx, dy);
+        self.inner.mouse_wheel_event(dx, dy);
     }
 
     fn mouse_button_down_event(&mut self, mb: mq::MouseButton, x: f32, y: f32) {
-        self.0.mouse_button_down_event(mb, x, y);
+        let (x, y) = scale_point(x, y);
+        self.inner.mouse_button_down_event(mb, x, y);
     }
 
     fn mouse_button_up_event(&mut self, mb: mq::MouseButton, x: f32, y: f32) {
-        self.0.mouse_button_up_event(mb, x, y);
+        let (x, y) = scale_point(x, y);
+        self.inner.mouse_button_up_event(mb, x, y);
     }
 
     fn char_event(&mut self, character: char, keymods: mq::KeyMods, repeat: bool) {
-        self.0.char_event(character, keymods, repeat);
+        self.inner.char_event(character, keymods, repeat);
     }
 
     fn key_down_event(&mut self, keycode: mq::KeyCode, keymods: mq::KeyMods, repeat: bool) {
-        self.0.key_down_event(keycode, keymods, repeat);
+        self.inner.key_down_event(keycode, keymods, repeat);
     }
 
     fn key_up_event(&mut self, keycode: mq::KeyCode, keymods: mq::KeyMods) {
-        self.0.key_up_event(keycode, keymods);
+        self.inner.key_up_event(keycode, keymods);
+    }
+
+    fn touch_event(&mut self, phase: mq::TouchPhase, id: u64, x: f32, y: f32) {
+        let (x, y) = scale_point(x, y);
+        match phase {
+            mq::TouchPhase::Started => {
+                // Only the first finger down drives yakui; ignore the rest so a
+                // second touch can't steal or clobber the active pointer.
+                if self.touch_id.is_none() {
+                    self.touch_id = Some(id);
+                    self.inner.mouse_motion_event(x, y);
+                    self.inner
+                        .mouse_button_down_event(mq::MouseButton::Left, x, y);
+                }
+            }
+            mq::TouchPhase::Moved => {
+                if self.touch_id == Some(id) {
+                    self.inner.mouse_motion_event(x, y);
+                }
+            }
+            mq::TouchPhase::Ended | mq::TouchPhase::Cancelled => {
+                if self.touch_id == Some(id) {
+                    self.inner
+                        .mouse_button_up_event(mq::MouseButton::Left, x, y);
+                    self.touch_id = None;
+                }
+            }
+        }
     }
 }